@@ -0,0 +1,41 @@
+use chrono::{Duration, Utc};
+
+/// How much invocation time must remain before the deadline for maintenance
+/// work to start another unit of work, unless `PURGE_DEADLINE_SAFETY_MARGIN_MS`
+/// is set. Large enough to finish persisting a checkpoint and return cleanly.
+const DEFAULT_DEADLINE_SAFETY_MARGIN_MILLIS: i64 = 10_000;
+
+/// Reads the `PURGE_DEADLINE_SAFETY_MARGIN_MS` env var, falling back to
+/// [DEFAULT_DEADLINE_SAFETY_MARGIN_MILLIS] when unset or invalid.
+fn deadline_safety_margin() -> Duration {
+    std::env::var("PURGE_DEADLINE_SAFETY_MARGIN_MS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(Duration::milliseconds)
+        .unwrap_or_else(|| Duration::milliseconds(DEFAULT_DEADLINE_SAFETY_MARGIN_MILLIS))
+}
+
+/// Tracks the remaining budget before the Lambda invocation's hard timeout,
+/// derived from the `LambdaEvent` context deadline.
+#[derive(Debug, Clone, Copy)]
+pub struct PurgeDeadline {
+    deadline_epoch_millis: i64,
+}
+
+impl PurgeDeadline {
+    pub fn new(deadline_epoch_millis: i64) -> Self {
+        Self {
+            deadline_epoch_millis,
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        Duration::milliseconds(self.deadline_epoch_millis - Utc::now().timestamp_millis())
+    }
+
+    /// Whether there's still enough budget left to safely start another
+    /// unit of work before the invocation is cut off.
+    pub fn has_budget(&self) -> bool {
+        self.remaining() > deadline_safety_margin()
+    }
+}
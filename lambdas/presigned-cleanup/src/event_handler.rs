@@ -1,12 +1,32 @@
-use std::sync::Arc;
+//! The `PurgeRunCursor` checkpoint this handler reads and writes depends on a
+//! `docbox_database::models::purge_run_cursor::PurgeRunCursor` model that is
+//! not part of this crate and is not included in this snapshot of the
+//! workspace; see the equivalent note in `maintenance::presigned_purge`.
+
+use std::{collections::BTreeMap, sync::Arc};
 
 use aws_config::SdkConfig;
 use aws_lambda_events::event::eventbridge::EventBridgeEvent;
-use docbox_database::{DatabasePoolCache, DatabasePoolCacheConfig};
+use docbox_database::{
+    models::{
+        purge_run_cursor::PurgeRunCursor,
+        tenant::{Tenant, TenantId},
+    },
+    DatabasePoolCache, DatabasePoolCacheConfig,
+};
 use docbox_storage::StorageLayerFactory;
+use futures::stream::{self, StreamExt};
 use lambda_runtime::{tracing, Error, LambdaEvent};
+use thiserror::Error as ThisError;
 use tokio::sync::OnceCell;
 
+use crate::deadline::PurgeDeadline;
+use crate::maintenance::{all_maintenance_tasks, MaintenanceTask, TaskReport};
+
+/// Default number of tenants processed concurrently when
+/// `PURGE_TENANT_CONCURRENCY` is not set.
+const DEFAULT_PURGE_TENANT_CONCURRENCY: usize = 8;
+
 static DEPENDENCIES: OnceCell<Dependencies> = OnceCell::new();
 
 pub struct Dependencies {
@@ -48,86 +68,266 @@ async fn function_handler(
     event: LambdaEvent<EventBridgeEvent>,
     dependencies: &Dependencies,
 ) -> Result<(), Error> {
-    // Run the presigned purge
-    if let Err(error) = purge_expired_presigned_tasks(&dependencies.db, &dependencies.storage).await
+    let deadline = PurgeDeadline::new(event.context.deadline as i64);
+    let requested_tasks = requested_task_names(&event.payload);
+
+    if let Err(error) = run_maintenance_tasks(
+        &dependencies.db_cache,
+        &dependencies.storage,
+        deadline,
+        requested_tasks,
+    )
+    .await
     {
-        tracing::error!(?error, "failed to purge presigned tasks");
+        tracing::error!(?error, "failed to run maintenance tasks");
     }
 
     Ok(())
 }
 
-/// Purge the presigned tasks for all tenants
+/// Reads the set of task names to run from the EventBridge `detail` payload,
+/// e.g. `{"tasks": ["presigned_purge"]}`. `None` means "run every registered
+/// task", so a rule that omits `tasks` keeps running the full set.
+fn requested_task_names(event: &EventBridgeEvent) -> Option<Vec<String>> {
+    let names = event
+        .detail
+        .get("tasks")?
+        .as_array()?
+        .iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect::<Vec<_>>();
+
+    Some(names)
+}
+
+/// Errors that can occur while running the registered maintenance tasks
+#[derive(Debug, ThisError)]
+pub enum RunMaintenanceTasksError {
+    #[error("failed to connect to database")]
+    ConnectDatabase,
+    #[error("failed to query available tenants")]
+    QueryTenants,
+    #[error("failed to persist maintenance run checkpoint")]
+    PersistCursor,
+}
+
+/// Reads the `PURGE_TENANT_CONCURRENCY` env var, falling back to
+/// [DEFAULT_PURGE_TENANT_CONCURRENCY] when unset or invalid.
+fn purge_tenant_concurrency() -> usize {
+    std::env::var("PURGE_TENANT_CONCURRENCY")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&value| value > 0)
+        .unwrap_or(DEFAULT_PURGE_TENANT_CONCURRENCY)
+}
+
+/// Aggregate outcome of a single [MaintenanceTask] across all tenants
+#[derive(Debug, Default)]
+struct TaskSummary {
+    attempted: usize,
+    completed: usize,
+    metrics: BTreeMap<&'static str, usize>,
+}
+
+/// Derives a stable checkpoint key for this invocation's selected task set,
+/// so different EventBridge rules scheduling different task subsets don't
+/// clobber each other's progress cursor.
+fn run_name(tasks: &[Box<dyn MaintenanceTask>]) -> String {
+    let mut names: Vec<&str> = tasks.iter().map(|task| task.name()).collect();
+    names.sort_unstable();
+    names.join(",")
+}
+
+/// Run every selected [MaintenanceTask] against every tenant.
+///
+/// Tenants are processed concurrently, bounded by `PURGE_TENANT_CONCURRENCY`,
+/// so one slow or faulty tenant database does not stall the whole run while
+/// still capping how many tenant database pools are checked out at once.
+/// Work stops once the remaining invocation budget drops below the safety
+/// margin; the lowest tenant id left incomplete is persisted as the
+/// [PurgeRunCursor] checkpoint so the next scheduled invocation resumes there
+/// instead of restarting from the top. Presigned purge is the first
+/// registered task; future maintenance tasks plug into
+/// [crate::maintenance::all_maintenance_tasks] without this loop changing.
 #[tracing::instrument(skip_all)]
-async fn purge_expired_presigned_tasks(
+async fn run_maintenance_tasks(
     db_cache: &'static Arc<DatabasePoolCache>,
-    storage: &'static StorageLayerFactory,
-) -> Result<(), PurgeExpiredPresignedError> {
+    storage_factory: &'static StorageLayerFactory,
+    deadline: PurgeDeadline,
+    requested_tasks: Option<Vec<String>>,
+) -> Result<(), RunMaintenanceTasksError> {
+    let tasks: Vec<Box<dyn MaintenanceTask>> = all_maintenance_tasks(deadline)
+        .into_iter()
+        .filter(|task| {
+            requested_tasks
+                .as_ref()
+                .map(|names| names.iter().any(|name| name == task.name()))
+                .unwrap_or(true)
+        })
+        .collect();
+
+    if tasks.is_empty() {
+        tracing::warn!(
+            ?requested_tasks,
+            "no registered maintenance task matched this invocation"
+        );
+        return Ok(());
+    }
+
+    let run_name = run_name(&tasks);
+
     let db = db_cache.get_root_pool().await.map_err(|error| {
         tracing::error!(?error, "failed to connect to root database");
-        PurgeExpiredPresignedError::ConnectDatabase
+        RunMaintenanceTasksError::ConnectDatabase
     })?;
 
     let tenants = Tenant::all(&db).await.map_err(|error| {
         tracing::error!(?error, "failed to query available tenants");
-        PurgeExpiredPresignedError::QueryTenants
+        RunMaintenanceTasksError::QueryTenants
     })?;
 
-    // Early drop the root database pool access
-    drop(db);
+    let cursor = PurgeRunCursor::get(&db, &run_name).await.map_err(|error| {
+        tracing::error!(?error, "failed to load maintenance run checkpoint");
+        RunMaintenanceTasksError::ConnectDatabase
+    })?;
 
-    for tenant in tenants {
-        // Create the database connection pool
-        let db = db_cache.get_tenant_pool(&tenant).await.map_err(|error| {
-            tracing::error!(?error, "failed to connect to tenant database");
-            PurgeExpiredPresignedError::ConnectDatabase
-        })?;
-
-        let storage = storage.create_storage_layer(&tenant);
-
-        if let Err(cause) = purge_expired_presigned_tasks_tenant(&db, &storage).await {
-            tracing::error!(
-                ?cause,
-                ?tenant,
-                "failed to purge presigned tasks for tenant"
-            );
+    // Resume from the tenant we were last working on, if any. If that tenant
+    // is no longer in the list (e.g. offboarded between invocations),
+    // `skip_while` would drop the entire list instead of just the tenants
+    // before it, so fall back to processing everyone from the start.
+    let tenants = match cursor {
+        Some(cursor_tenant_id) => {
+            match tenants
+                .iter()
+                .position(|tenant| tenant.id == cursor_tenant_id)
+            {
+                Some(index) => tenants.into_iter().skip(index).collect::<Vec<_>>(),
+                None => {
+                    tracing::warn!(
+                        ?cursor_tenant_id,
+                        "maintenance run checkpoint tenant no longer exists, restarting from the top"
+                    );
+                    tenants
+                }
+            }
         }
-    }
+        None => tenants,
+    };
 
-    Ok(())
-}
+    // Early drop the root database pool access
+    drop(db);
 
-/// Purge the presigned tasks for a specific tenant
-async fn purge_expired_presigned_tasks_tenant(
-    db: &DbPool,
-    storage: &TenantStorageLayer,
-) -> DbResult<()> {
-    let current_date = Utc::now();
-    let tasks = PresignedUploadTask::find_expired(db, current_date).await?;
-    if tasks.is_empty() {
-        return Ok(());
-    }
+    let concurrency = purge_tenant_concurrency();
+    let tasks = &tasks;
 
-    for task in tasks {
-        // Delete the task itself
-        if let Err(error) = PresignedUploadTask::delete(db, task.id).await {
-            tracing::error!(?error, "failed to delete presigned upload task");
-        }
+    let (checkpoint_tenant_id, summaries) = stream::iter(tenants)
+        .map(|tenant| async move {
+            let tenant_id = tenant.id;
 
-        // Delete incomplete file uploads
-        match task.status {
-            PresignedTaskStatus::Completed { .. } => {
-                // Upload completed, nothing to revert
+            if !deadline.has_budget() {
+                return (tenant_id, false, Vec::new());
             }
-            PresignedTaskStatus::Failed { .. } | PresignedTaskStatus::Pending => {
-                if let Err(error) = storage.delete_file(&task.file_key).await {
-                    tracing::error!(
-                        ?error,
-                        "failed to delete expired presigned task file from tenant"
-                    );
+
+            // Create the database connection pool
+            let db = match db_cache.get_tenant_pool(&tenant).await {
+                Ok(db) => db,
+                Err(error) => {
+                    tracing::error!(?error, ?tenant, "failed to connect to tenant database");
+                    return (tenant_id, true, Vec::new());
+                }
+            };
+
+            let storage = storage_factory.create_storage_layer(&tenant);
+
+            let mut reports = Vec::with_capacity(tasks.len());
+            let mut tenant_completed = true;
+
+            for task in tasks.iter() {
+                match task.run(&db, &storage).await {
+                    Ok(report) => {
+                        tenant_completed &= report.completed;
+                        reports.push((task.name(), report));
+                    }
+                    Err(cause) => {
+                        tracing::error!(
+                            ?cause,
+                            ?tenant,
+                            task = task.name(),
+                            "maintenance task failed for tenant"
+                        );
+                        // A task error is not a deadline truncation: it must
+                        // not pin the `PurgeRunCursor` checkpoint, or a
+                        // recurring error on one tenant would starve every
+                        // lower-id tenant of maintenance indefinitely. It is
+                        // still logged above and counted as not-completed in
+                        // the per-task summary below.
+                        reports.push((task.name(), TaskReport::default()));
+                    }
                 }
             }
-        }
+
+            (tenant_id, tenant_completed, reports)
+        })
+        .buffer_unordered(concurrency)
+        .fold(
+            (
+                None::<TenantId>,
+                BTreeMap::<&'static str, TaskSummary>::new(),
+            ),
+            |(mut checkpoint, mut summaries), (tenant_id, completed, reports)| async move {
+                if !completed {
+                    checkpoint = Some(match checkpoint {
+                        Some(existing) if existing < tenant_id => existing,
+                        _ => tenant_id,
+                    });
+                }
+
+                for (name, report) in reports {
+                    let summary = summaries.entry(name).or_default();
+                    summary.attempted += 1;
+                    if report.completed {
+                        summary.completed += 1;
+                    }
+                    for (metric, value) in report.metrics {
+                        *summary.metrics.entry(metric).or_insert(0) += value;
+                    }
+                }
+
+                (checkpoint, summaries)
+            },
+        )
+        .await;
+
+    for (task_name, summary) in &summaries {
+        tracing::info!(
+            task = task_name,
+            attempted = summary.attempted,
+            completed = summary.completed,
+            metrics = ?summary.metrics,
+            "completed maintenance task across tenants"
+        );
+    }
+
+    // Re-acquire the root pool to persist (or clear) the checkpoint now the
+    // per-tenant pools have been dropped.
+    let db = db_cache.get_root_pool().await.map_err(|error| {
+        tracing::error!(?error, "failed to connect to root database");
+        RunMaintenanceTasksError::ConnectDatabase
+    })?;
+
+    match checkpoint_tenant_id {
+        Some(tenant_id) => PurgeRunCursor::set(&db, &run_name, tenant_id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, "failed to persist maintenance run checkpoint");
+                RunMaintenanceTasksError::PersistCursor
+            })?,
+        None => PurgeRunCursor::clear(&db, &run_name)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, "failed to clear maintenance run checkpoint");
+                RunMaintenanceTasksError::PersistCursor
+            })?,
     }
 
     Ok(())
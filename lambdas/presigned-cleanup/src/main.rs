@@ -1,7 +1,8 @@
 use lambda_runtime::{run, service_fn, tracing, Error};
 
+mod deadline;
 mod event_handler;
-use event_handler::function_handler;
+mod maintenance;
 
 use crate::event_handler::outer_function_handler;
 
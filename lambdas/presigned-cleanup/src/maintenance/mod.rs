@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use docbox_database::DbPool;
+use docbox_storage::TenantStorageLayer;
+use thiserror::Error as ThisError;
+
+mod presigned_purge;
+
+pub use presigned_purge::PresignedPurgeTask;
+
+use crate::deadline::PurgeDeadline;
+
+/// A single unit of scheduled upkeep the EventBridge-triggered Lambda can run
+/// against a tenant (e.g. presigned purge, expired-token pruning, orphan
+/// reconciliation). New tasks implement this trait and register themselves in
+/// [all_maintenance_tasks] without the dispatch loop in `event_handler`
+/// needing to change.
+#[async_trait]
+pub trait MaintenanceTask: Send + Sync {
+    /// Stable identifier used to select this task from the EventBridge rule's
+    /// `detail`, and as the key in the aggregated run summary.
+    fn name(&self) -> &'static str;
+
+    /// Run this task against a single tenant's database and storage layer.
+    async fn run(
+        &self,
+        db: &DbPool,
+        storage: &TenantStorageLayer,
+    ) -> Result<TaskReport, MaintenanceTaskError>;
+}
+
+/// Errors a [MaintenanceTask] can surface back to the dispatch loop
+#[derive(Debug, ThisError)]
+pub enum MaintenanceTaskError {
+    #[error("database error: {0}")]
+    Database(#[from] docbox_database::DbError),
+    #[error("storage error: {0}")]
+    Storage(#[from] docbox_storage::StorageError),
+}
+
+/// Structured result of running a single [MaintenanceTask] against one
+/// tenant, folded by the dispatch loop into the overall run summary.
+#[derive(Debug, Default)]
+pub struct TaskReport {
+    pub completed: bool,
+    pub metrics: BTreeMap<&'static str, usize>,
+}
+
+impl TaskReport {
+    pub fn completed() -> Self {
+        Self {
+            completed: true,
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_metric(mut self, key: &'static str, value: usize) -> Self {
+        self.metrics.insert(key, value);
+        self
+    }
+}
+
+/// Every [MaintenanceTask] this Lambda knows how to run, in registration
+/// order. The incoming EventBridge `detail` narrows this down to the subset a
+/// given scheduled rule should trigger; see `event_handler::requested_task_names`.
+pub fn all_maintenance_tasks(deadline: PurgeDeadline) -> Vec<Box<dyn MaintenanceTask>> {
+    vec![Box::new(PresignedPurgeTask::new(deadline))]
+}
@@ -0,0 +1,678 @@
+//! This task, and the run-cursor checkpoint in `event_handler`, depend on a
+//! handful of `docbox_database`/`docbox_storage` additions that are not part
+//! of this crate and are not included in this snapshot of the workspace:
+//! `models::file::File::exists_for_key`, `models::purge_failure::PurgeFailure`
+//! and its `needs_object_cleanup` column, `models::purge_run_cursor::PurgeRunCursor`,
+//! a `multipart_upload_id` column on `PresignedUploadTask`, and the
+//! `TenantStorageLayer::{list_presigned_objects, list_multipart_uploads,
+//! abort_multipart_upload}` methods. They're assumed to live in the
+//! `docbox_database`/`docbox_storage` crates alongside the other models and
+//! storage methods this task already calls; until those land, this task does
+//! not build.
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use docbox_database::{
+    models::{
+        file::File,
+        presigned_upload_task::{PresignedTaskStatus, PresignedUploadTask},
+        purge_failure::PurgeFailure,
+    },
+    DbPool, DbResult,
+};
+use docbox_storage::TenantStorageLayer;
+use lambda_runtime::tracing;
+
+use crate::deadline::PurgeDeadline;
+
+use super::{MaintenanceTask, MaintenanceTaskError, TaskReport};
+
+/// Env var gating the storage-vs-DB orphan reconciliation pass. Left off by
+/// default so the cheaper expiry-only purge remains the norm.
+const RECONCILE_ORPHANED_FILES_ENV: &str = "RECONCILE_ORPHANED_FILES";
+
+/// Default grace window an object must have sat in storage with no DB
+/// reference before it is considered safe to delete as an orphan.
+const DEFAULT_ORPHAN_GRACE_SECONDS: i64 = 24 * 60 * 60;
+
+/// Default number of attempts a failed deletion gets before it is moved to
+/// the dead-letter state, used unless `PURGE_FAILURE_MAX_ATTEMPTS` is set.
+const DEFAULT_PURGE_FAILURE_MAX_ATTEMPTS: u32 = 5;
+
+/// Base delay used for the exponential backoff applied between retries of a
+/// failed deletion: `base * 2^attempt`, capped at [PURGE_FAILURE_MAX_BACKOFF_SECONDS].
+const PURGE_FAILURE_BASE_BACKOFF_SECONDS: i64 = 30;
+
+/// Upper bound on the backoff delay between retries of a failed deletion.
+const PURGE_FAILURE_MAX_BACKOFF_SECONDS: i64 = 6 * 60 * 60;
+
+/// Reads the `RECONCILE_ORPHANED_FILES` env var.
+fn reconciliation_enabled() -> bool {
+    std::env::var(RECONCILE_ORPHANED_FILES_ENV)
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Reads the `ORPHAN_GRACE_WINDOW_SECONDS` env var, falling back to
+/// [DEFAULT_ORPHAN_GRACE_SECONDS] when unset or invalid.
+fn orphan_grace_window() -> Duration {
+    std::env::var("ORPHAN_GRACE_WINDOW_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(Duration::seconds)
+        .unwrap_or_else(|| Duration::seconds(DEFAULT_ORPHAN_GRACE_SECONDS))
+}
+
+/// Reads the `PURGE_FAILURE_MAX_ATTEMPTS` env var, falling back to
+/// [DEFAULT_PURGE_FAILURE_MAX_ATTEMPTS] when unset or invalid.
+fn purge_failure_max_attempts() -> u32 {
+    std::env::var("PURGE_FAILURE_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&value| value > 0)
+        .unwrap_or(DEFAULT_PURGE_FAILURE_MAX_ATTEMPTS)
+}
+
+/// Exponential backoff delay before the next retry of a failed deletion,
+/// `base * 2^attempts` capped at [PURGE_FAILURE_MAX_BACKOFF_SECONDS].
+fn purge_failure_backoff(attempts: u32) -> Duration {
+    let seconds = PURGE_FAILURE_BASE_BACKOFF_SECONDS.saturating_mul(1i64 << attempts.min(20));
+    Duration::seconds(seconds.min(PURGE_FAILURE_MAX_BACKOFF_SECONDS))
+}
+
+/// Which cleanup step failed for a `purge_failures` row, recorded as its
+/// `reason` so [drain_due_purge_failures_tenant] knows which operation to
+/// retry instead of always retrying the storage object delete. A task whose
+/// row failed to delete needs the row deletion retried, not the (already
+/// successful, or irrelevant) object delete, otherwise the two code paths
+/// fight over the same file key forever.
+enum PurgeFailureKind {
+    PresignedTaskRow,
+    StorageObject,
+    MultipartUpload,
+}
+
+impl PurgeFailureKind {
+    fn reason(&self) -> &'static str {
+        match self {
+            PurgeFailureKind::PresignedTaskRow => "failed to delete presigned upload task",
+            PurgeFailureKind::StorageObject => "failed to delete file from storage",
+            PurgeFailureKind::MultipartUpload => "failed to abort multipart upload(s) for file",
+        }
+    }
+
+    fn from_reason(reason: &str) -> Option<Self> {
+        match reason {
+            "failed to delete presigned upload task" => Some(Self::PresignedTaskRow),
+            "failed to delete file from storage" => Some(Self::StorageObject),
+            "failed to abort multipart upload(s) for file" => Some(Self::MultipartUpload),
+            _ => None,
+        }
+    }
+}
+
+/// Records a failed cleanup step against the `purge_failures` table,
+/// rescheduling it with exponential backoff or moving it to the dead-letter
+/// state once `PURGE_FAILURE_MAX_ATTEMPTS` is reached.
+///
+/// `needs_object_cleanup` records whether the multipart-abort/object-delete
+/// steps still need to run for this file key once the recorded step
+/// eventually retries successfully, so [drain_due_purge_failures_tenant] can
+/// cascade into them instead of treating the file key as fully reclaimed
+/// after only its first failed step is retried.
+///
+/// Returns `true` if the failure was dead-lettered on this call.
+async fn record_purge_failure(
+    db: &DbPool,
+    file_key: &str,
+    kind: PurgeFailureKind,
+    needs_object_cleanup: bool,
+) -> DbResult<bool> {
+    let failure =
+        PurgeFailure::upsert_failure(db, file_key, kind.reason(), needs_object_cleanup).await?;
+
+    if failure.attempts >= purge_failure_max_attempts() {
+        PurgeFailure::mark_dead_letter(db, failure.id).await?;
+        return Ok(true);
+    }
+
+    let next_attempt_at = Utc::now() + purge_failure_backoff(failure.attempts);
+    PurgeFailure::reschedule(db, failure.id, next_attempt_at).await?;
+
+    Ok(false)
+}
+
+/// Outcome of draining due purge-failure retries for a single tenant
+#[derive(Debug, Default)]
+struct RetryDrainStats {
+    retried_succeeded: usize,
+    retried_failed: usize,
+    dead_lettered: usize,
+}
+
+/// How a single due retry resolved, distinguishing a plain pass/fail of the
+/// recorded step from one that succeeded but cascaded into a later pipeline
+/// step that itself failed — that later step has already recorded (or
+/// dead-lettered) its own `purge_failures` row, so the original row must not
+/// also be rescheduled.
+enum DrainOutcome {
+    Succeeded,
+    Failed,
+    Cascaded { dead_lettered: usize },
+}
+
+/// Retry deletions recorded in `purge_failures` whose backoff has elapsed.
+///
+/// A successful retry clears the failure row; a repeated failure reschedules
+/// it with the next backoff delay, or moves it to the dead-letter state once
+/// `PURGE_FAILURE_MAX_ATTEMPTS` is reached so it stops being retried and is
+/// surfaced in the run summary for manual inspection instead.
+///
+/// Which operation is retried is driven by the row's recorded
+/// [PurgeFailureKind] reason, not a blanket storage object delete, so a
+/// presigned task row that failed to delete is retried as a row deletion
+/// rather than being silently skipped forever. A step whose retry succeeds
+/// but that still has later pipeline steps outstanding (tracked via
+/// `needs_object_cleanup`) cascades into them via [cleanup_task_storage]
+/// instead of declaring the file key reclaimed after only one step runs.
+async fn drain_due_purge_failures_tenant(
+    db: &DbPool,
+    storage: &TenantStorageLayer,
+) -> DbResult<RetryDrainStats> {
+    let max_attempts = purge_failure_max_attempts();
+    let due = PurgeFailure::find_due(db, Utc::now()).await?;
+
+    let mut stats = RetryDrainStats::default();
+
+    for failure in due {
+        let outcome = match PurgeFailureKind::from_reason(&failure.reason) {
+            Some(PurgeFailureKind::PresignedTaskRow) => {
+                match PresignedUploadTask::delete_by_file_key(db, &failure.file_key).await {
+                    Ok(()) if !failure.needs_object_cleanup => DrainOutcome::Succeeded,
+                    Ok(()) => {
+                        match cleanup_task_storage(db, storage, &failure.file_key, None).await? {
+                            ObjectCleanupOutcome::Cleaned => DrainOutcome::Succeeded,
+                            ObjectCleanupOutcome::MultipartFailed { dead_lettered }
+                            | ObjectCleanupOutcome::ObjectDeleteFailed { dead_lettered } => {
+                                DrainOutcome::Cascaded { dead_lettered }
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(
+                            ?error,
+                            key = %failure.file_key,
+                            "retry of failed presigned task row deletion failed again"
+                        );
+                        DrainOutcome::Failed
+                    }
+                }
+            }
+            Some(PurgeFailureKind::MultipartUpload) => {
+                match storage.list_multipart_uploads(&failure.file_key).await {
+                    Ok(dangling_upload_ids) => {
+                        let mut all_aborted = true;
+                        for upload_id in dangling_upload_ids {
+                            if let Err(error) = storage
+                                .abort_multipart_upload(&failure.file_key, &upload_id)
+                                .await
+                            {
+                                tracing::error!(
+                                    ?error,
+                                    key = %failure.file_key,
+                                    "retry of dangling multipart upload abort failed again"
+                                );
+                                all_aborted = false;
+                            }
+                        }
+
+                        if !all_aborted {
+                            DrainOutcome::Failed
+                        } else if let Err(error) = storage.delete_file(&failure.file_key).await {
+                            tracing::error!(
+                                ?error,
+                                key = %failure.file_key,
+                                "failed to delete file from storage after retrying its multipart abort"
+                            );
+
+                            let dead_lettered = if record_purge_failure(
+                                db,
+                                &failure.file_key,
+                                PurgeFailureKind::StorageObject,
+                                true,
+                            )
+                            .await?
+                            {
+                                1
+                            } else {
+                                0
+                            };
+
+                            DrainOutcome::Cascaded { dead_lettered }
+                        } else {
+                            DrainOutcome::Succeeded
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(
+                            ?error,
+                            key = %failure.file_key,
+                            "retry of multipart upload listing failed again"
+                        );
+                        DrainOutcome::Failed
+                    }
+                }
+            }
+            // Unrecognised or storage-object reason: fall back to retrying
+            // the plain object delete, the original behaviour.
+            Some(PurgeFailureKind::StorageObject) | None => {
+                match storage.delete_file(&failure.file_key).await {
+                    Ok(()) => DrainOutcome::Succeeded,
+                    Err(error) => {
+                        tracing::error!(
+                            ?error,
+                            key = %failure.file_key,
+                            "retry of failed purge deletion failed again"
+                        );
+                        DrainOutcome::Failed
+                    }
+                }
+            }
+        };
+
+        match outcome {
+            DrainOutcome::Succeeded => {
+                PurgeFailure::delete(db, failure.id).await?;
+                stats.retried_succeeded += 1;
+                continue;
+            }
+            DrainOutcome::Cascaded { dead_lettered } => {
+                stats.dead_lettered += dead_lettered;
+                stats.retried_failed += 1;
+                continue;
+            }
+            DrainOutcome::Failed => {}
+        }
+
+        if failure.attempts + 1 >= max_attempts {
+            PurgeFailure::mark_dead_letter(db, failure.id).await?;
+            stats.dead_lettered += 1;
+        } else {
+            let next_attempt_at = Utc::now() + purge_failure_backoff(failure.attempts + 1);
+            PurgeFailure::reschedule(db, failure.id, next_attempt_at).await?;
+        }
+
+        stats.retried_failed += 1;
+    }
+
+    Ok(stats)
+}
+
+/// Outcome of purging the expired presigned tasks for a single tenant
+struct TenantPurgeOutcome {
+    dead_lettered: usize,
+    /// `false` if the deadline was hit partway through and some expired
+    /// tasks were left for the next invocation to pick back up.
+    completed: bool,
+}
+
+/// Outcome of aborting the dangling multipart uploads for a single task
+struct MultipartAbortOutcome {
+    /// `true` if at least one abort or listing call failed and was recorded
+    /// in `purge_failures` for retry.
+    failed: bool,
+    dead_lettered: usize,
+}
+
+/// Aborts any in-flight S3 multipart upload left behind by an expired
+/// presigned task.
+///
+/// A plain object delete does not clean up multipart uploads that were
+/// initiated but never completed, leaving behind incomplete-upload parts
+/// that still accrue storage cost. The `upload_id` recorded on the task when
+/// the presign was issued for a multipart flow is aborted directly, and any
+/// other dangling uploads still registered against the key are also listed
+/// and aborted so a lost or never-recorded id doesn't leak parts forever.
+///
+/// A failure here is recorded in `purge_failures` the same way a failed
+/// object delete is, so a transient error (e.g. S3 throttling) is retried
+/// with backoff instead of silently leaking the multipart parts.
+async fn abort_incomplete_multipart_uploads(
+    db: &DbPool,
+    storage: &TenantStorageLayer,
+    file_key: &str,
+    multipart_upload_id: Option<&str>,
+) -> DbResult<MultipartAbortOutcome> {
+    let mut failed = false;
+
+    if let Some(upload_id) = multipart_upload_id {
+        if let Err(error) = storage.abort_multipart_upload(file_key, upload_id).await {
+            tracing::error!(
+                ?error,
+                key = %file_key,
+                "failed to abort multipart upload for expired presigned task"
+            );
+            failed = true;
+        }
+    }
+
+    match storage.list_multipart_uploads(file_key).await {
+        Ok(dangling_upload_ids) => {
+            for upload_id in dangling_upload_ids {
+                if let Err(error) = storage.abort_multipart_upload(file_key, &upload_id).await {
+                    tracing::error!(
+                        ?error,
+                        key = %file_key,
+                        "failed to abort dangling multipart upload for expired presigned task"
+                    );
+                    failed = true;
+                }
+            }
+        }
+        Err(error) => {
+            tracing::error!(
+                ?error,
+                key = %file_key,
+                "failed to list multipart uploads for expired presigned task"
+            );
+            failed = true;
+        }
+    }
+
+    if !failed {
+        return Ok(MultipartAbortOutcome {
+            failed: false,
+            dead_lettered: 0,
+        });
+    }
+
+    // The object delete that would normally follow still needs to run once
+    // this step is retried successfully.
+    let dead_lettered =
+        if record_purge_failure(db, file_key, PurgeFailureKind::MultipartUpload, true).await? {
+            1
+        } else {
+            0
+        };
+
+    Ok(MultipartAbortOutcome {
+        failed: true,
+        dead_lettered,
+    })
+}
+
+/// Outcome of running the post-row-delete storage cleanup (multipart abort
+/// sweep + object delete) for a single file key.
+enum ObjectCleanupOutcome {
+    /// The multipart abort/listing step failed; recorded in `purge_failures`
+    /// for retry. The object delete was not attempted this pass.
+    MultipartFailed { dead_lettered: usize },
+    /// Multipart cleanup succeeded but the object delete failed; recorded in
+    /// `purge_failures` for retry.
+    ObjectDeleteFailed { dead_lettered: usize },
+    /// Both steps succeeded; the file key is fully reclaimed.
+    Cleaned,
+}
+
+/// Runs the multipart-abort-then-object-delete pipeline for a file key whose
+/// `PresignedUploadTask` row has already been removed, used both for
+/// `Pending`/`Failed` tasks in the main purge loop and to resume the
+/// remaining pipeline steps in [drain_due_purge_failures_tenant] after a
+/// retried step succeeds.
+async fn cleanup_task_storage(
+    db: &DbPool,
+    storage: &TenantStorageLayer,
+    file_key: &str,
+    multipart_upload_id: Option<&str>,
+) -> DbResult<ObjectCleanupOutcome> {
+    let abort_outcome =
+        abort_incomplete_multipart_uploads(db, storage, file_key, multipart_upload_id).await?;
+    if abort_outcome.failed {
+        return Ok(ObjectCleanupOutcome::MultipartFailed {
+            dead_lettered: abort_outcome.dead_lettered,
+        });
+    }
+
+    if let Err(error) = storage.delete_file(file_key).await {
+        tracing::error!(
+            ?error,
+            key = %file_key,
+            "failed to delete expired presigned task file from tenant"
+        );
+
+        let dead_lettered =
+            if record_purge_failure(db, file_key, PurgeFailureKind::StorageObject, true).await? {
+                1
+            } else {
+                0
+            };
+
+        return Ok(ObjectCleanupOutcome::ObjectDeleteFailed { dead_lettered });
+    }
+
+    Ok(ObjectCleanupOutcome::Cleaned)
+}
+
+/// Purge the presigned tasks for a specific tenant.
+///
+/// Deletions that fail are not dropped: they're recorded in `purge_failures`
+/// for [drain_due_purge_failures_tenant] to retry with backoff on a later
+/// invocation. A task whose file key already has an active `purge_failures`
+/// row is skipped here entirely so this loop and the retry drain don't fight
+/// over the same cleanup step outside its backoff schedule. The remaining
+/// invocation budget is checked before each task so a large backlog stops
+/// cleanly instead of being cut off mid-batch; any tasks left unprocessed
+/// simply remain "expired" and are picked up again next run.
+async fn purge_expired_presigned_tasks_tenant(
+    db: &DbPool,
+    storage: &TenantStorageLayer,
+    deadline: PurgeDeadline,
+) -> DbResult<TenantPurgeOutcome> {
+    let current_date = Utc::now();
+    let tasks = PresignedUploadTask::find_expired(db, current_date).await?;
+    if tasks.is_empty() {
+        return Ok(TenantPurgeOutcome {
+            dead_lettered: 0,
+            completed: true,
+        });
+    }
+
+    let mut dead_lettered = 0;
+
+    for task in tasks {
+        if !deadline.has_budget() {
+            return Ok(TenantPurgeOutcome {
+                dead_lettered,
+                completed: false,
+            });
+        }
+
+        // Already queued for retry by drain_due_purge_failures_tenant on a
+        // later invocation; leave it alone until its backoff is due.
+        if PurgeFailure::exists_for_file_key(db, &task.file_key).await? {
+            continue;
+        }
+
+        // A task that's still Pending or Failed once expired has no
+        // completed upload to keep, so its storage still needs the
+        // multipart-abort-then-delete pipeline below; a Completed task's
+        // file is kept, so its row-delete failure has nothing left to
+        // cascade into if it's later retried successfully.
+        let needs_object_cleanup = !matches!(task.status, PresignedTaskStatus::Completed { .. });
+
+        // Delete the task itself
+        if let Err(error) = PresignedUploadTask::delete(db, task.id).await {
+            tracing::error!(?error, "failed to delete presigned upload task");
+
+            if record_purge_failure(
+                db,
+                &task.file_key,
+                PurgeFailureKind::PresignedTaskRow,
+                needs_object_cleanup,
+            )
+            .await?
+            {
+                dead_lettered += 1;
+            }
+
+            continue;
+        }
+
+        // Delete incomplete file uploads
+        match task.status {
+            PresignedTaskStatus::Completed { .. } => {
+                // Upload completed, nothing to revert
+            }
+            PresignedTaskStatus::Failed { .. } | PresignedTaskStatus::Pending => {
+                match cleanup_task_storage(
+                    db,
+                    storage,
+                    &task.file_key,
+                    task.multipart_upload_id.as_deref(),
+                )
+                .await?
+                {
+                    ObjectCleanupOutcome::Cleaned => {}
+                    ObjectCleanupOutcome::MultipartFailed { dead_lettered: n }
+                    | ObjectCleanupOutcome::ObjectDeleteFailed { dead_lettered: n } => {
+                        dead_lettered += n;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(TenantPurgeOutcome {
+        dead_lettered,
+        completed: true,
+    })
+}
+
+/// Outcome of reconciling orphaned storage objects for a single tenant
+struct ReconcileOrphanedFilesOutcome {
+    /// `false` if the deadline was hit partway through pagination and some
+    /// storage pages were left unvisited.
+    completed: bool,
+}
+
+/// Reconcile files left behind in object storage with no corresponding
+/// database reference, e.g. after a crash between an upload finishing and the
+/// task/file record being committed.
+///
+/// Lists stored objects under the presigned upload prefix, paginating via
+/// continuation tokens, and deletes any key with no matching
+/// `PresignedUploadTask` row and no matching committed `File` record, that
+/// has also sat untouched for longer than the configured grace window (so
+/// in-flight uploads are never mistaken for orphans, and completed uploads
+/// whose task row has already been purged are never mistaken for orphans
+/// either). The remaining invocation budget is checked before fetching each
+/// page, the same way [purge_expired_presigned_tasks_tenant] checks it
+/// between tasks, so a tenant with a large orphan backlog stops cleanly
+/// instead of running the Lambda past its hard timeout.
+async fn reconcile_orphaned_files_tenant(
+    db: &DbPool,
+    storage: &TenantStorageLayer,
+    deadline: PurgeDeadline,
+) -> Result<ReconcileOrphanedFilesOutcome, MaintenanceTaskError> {
+    let grace_window = orphan_grace_window();
+    let now = Utc::now();
+
+    let mut continuation_token = None;
+
+    loop {
+        if !deadline.has_budget() {
+            return Ok(ReconcileOrphanedFilesOutcome { completed: false });
+        }
+
+        let page = storage
+            .list_presigned_objects(continuation_token.take())
+            .await?;
+
+        for object in page.objects {
+            if now - object.last_modified < grace_window {
+                // Too recent, may still be an in-flight upload
+                continue;
+            }
+
+            if PresignedUploadTask::exists_for_file_key(db, &object.key).await? {
+                continue;
+            }
+
+            // The task row is the only thing deleted when a presigned
+            // upload completes (see `purge_expired_presigned_tasks_tenant`,
+            // which keeps the object for `Completed` tasks). By the time its
+            // object clears the grace window here, that row is long gone, so
+            // a committed file record is the only remaining signal that this
+            // key is a real, already-committed user file rather than an
+            // orphan.
+            if File::exists_for_key(db, &object.key).await? {
+                continue;
+            }
+
+            if let Err(error) = storage.delete_file(&object.key).await {
+                tracing::error!(
+                    ?error,
+                    key = %object.key,
+                    "failed to delete orphaned storage object"
+                );
+            }
+        }
+
+        continuation_token = page.next_continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(ReconcileOrphanedFilesOutcome { completed: true })
+}
+
+/// Purges expired presigned upload tasks, draining any previously failed
+/// deletions first and optionally reconciling storage-vs-DB orphans.
+pub struct PresignedPurgeTask {
+    deadline: PurgeDeadline,
+}
+
+impl PresignedPurgeTask {
+    pub fn new(deadline: PurgeDeadline) -> Self {
+        Self { deadline }
+    }
+}
+
+#[async_trait]
+impl MaintenanceTask for PresignedPurgeTask {
+    fn name(&self) -> &'static str {
+        "presigned_purge"
+    }
+
+    async fn run(
+        &self,
+        db: &DbPool,
+        storage: &TenantStorageLayer,
+    ) -> Result<TaskReport, MaintenanceTaskError> {
+        let retry_stats = drain_due_purge_failures_tenant(db, storage).await?;
+
+        let purge_outcome =
+            purge_expired_presigned_tasks_tenant(db, storage, self.deadline).await?;
+
+        let mut completed = purge_outcome.completed;
+
+        if completed && reconciliation_enabled() {
+            let reconcile_outcome =
+                reconcile_orphaned_files_tenant(db, storage, self.deadline).await?;
+            completed = reconcile_outcome.completed;
+        }
+
+        let dead_lettered = retry_stats.dead_lettered + purge_outcome.dead_lettered;
+
+        let report = TaskReport {
+            completed,
+            ..TaskReport::default()
+        }
+        .with_metric("retried_succeeded", retry_stats.retried_succeeded)
+        .with_metric("retried_failed", retry_stats.retried_failed)
+        .with_metric("dead_lettered", dead_lettered);
+
+        Ok(report)
+    }
+}